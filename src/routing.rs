@@ -8,13 +8,14 @@ use bytes::Bytes;
 use futures_util::{future, ready};
 use http::{Method, Request, Response, StatusCode};
 use hyper::Body;
-use itertools::Itertools;
 use pin_project::pin_project;
 use regex::Regex;
 use std::{
-    borrow::Cow,
+    cell::RefCell,
+    collections::HashMap,
     convert::Infallible,
     future::Future,
+    mem,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
@@ -58,13 +59,47 @@ impl MethodFilter {
             _ => false,
         }
     }
+
+    /// The concrete [`Method`]s this filter accepts, for reporting in an
+    /// `Allow` header. `Any` expands to every method rather than matching
+    /// nothing.
+    fn http_methods(self) -> &'static [Method] {
+        match self {
+            MethodFilter::Any => &[
+                Method::CONNECT,
+                Method::DELETE,
+                Method::GET,
+                Method::HEAD,
+                Method::OPTIONS,
+                Method::PATCH,
+                Method::POST,
+                Method::PUT,
+                Method::TRACE,
+            ],
+            MethodFilter::Connect => std::slice::from_ref(&Method::CONNECT),
+            MethodFilter::Delete => std::slice::from_ref(&Method::DELETE),
+            MethodFilter::Get => std::slice::from_ref(&Method::GET),
+            MethodFilter::Head => std::slice::from_ref(&Method::HEAD),
+            MethodFilter::Options => std::slice::from_ref(&Method::OPTIONS),
+            MethodFilter::Patch => std::slice::from_ref(&Method::PATCH),
+            MethodFilter::Post => std::slice::from_ref(&Method::POST),
+            MethodFilter::Put => std::slice::from_ref(&Method::PUT),
+            MethodFilter::Trace => std::slice::from_ref(&Method::TRACE),
+        }
+    }
 }
 
+/// A router built on top of a radix tree over path segments.
+///
+/// `tree` holds every route that has been chained directly onto this value
+/// (via repeated calls to [`AddRoute::route`]); `fallback` is consulted only
+/// when the tree has no match for the request path, so an opaque router
+/// (such as a previously [`boxed`](Route::boxed) one) can still be composed
+/// underneath without being decomposed into the tree itself.
 #[derive(Clone)]
-pub struct Route<S, F> {
-    pub(crate) pattern: PathPattern,
-    pub(crate) svc: S,
+pub struct Route<F> {
     pub(crate) fallback: F,
+    pub(crate) tree: Arc<Node>,
 }
 
 #[derive(Clone)]
@@ -72,26 +107,96 @@ pub struct OnMethod<S, F> {
     pub(crate) method: MethodFilter,
     pub(crate) svc: S,
     pub(crate) fallback: F,
+    /// Every `MethodFilter` registered so far at this path, `self.method`
+    /// included. Kept alongside the fallback chain so that a miss can be
+    /// answered with `405 Method Not Allowed` and a correct `Allow` header
+    /// without having to fall all the way through to the path router's
+    /// `404` terminal.
+    pub(crate) allowed: Vec<MethodFilter>,
+}
+
+/// A predicate over a whole request, generalizing [`MethodFilter`] to
+/// arbitrary request properties — host header, a particular header's
+/// presence or value, content-type, a query predicate, and so on.
+pub trait Guard: Send + Sync + 'static {
+    fn check(&self, req: &Request<Body>) -> bool;
+}
+
+impl<F> Guard for F
+where
+    F: Fn(&Request<Body>) -> bool + Send + Sync + 'static,
+{
+    fn check(&self, req: &Request<Body>) -> bool {
+        (self)(req)
+    }
+}
+
+pub(crate) type BoxGuard = Arc<dyn Guard>;
+
+/// Routes to `svc` when `guard` passes, otherwise defers to `fallback` —
+/// the same shape as [`OnMethod`] but gated on an arbitrary [`Guard`]
+/// instead of a fixed [`MethodFilter`].
+#[derive(Clone)]
+pub struct OnGuard<S, F> {
+    pub(crate) guard: BoxGuard,
+    pub(crate) svc: S,
+    pub(crate) fallback: F,
+}
+
+impl<S, F> OnGuard<S, F> {
+    /// Chains another guarded route onto this one, tried if `self`'s guard
+    /// doesn't pass.
+    pub fn on<G, T>(self, guard: G, svc: T) -> OnGuard<T, Self>
+    where
+        G: Guard,
+    {
+        OnGuard {
+            guard: Arc::new(guard),
+            svc,
+            fallback: self,
+        }
+    }
 }
 
 pub trait AddRoute: Sized {
-    fn route<T>(self, spec: &str, svc: T) -> Route<T, Self>
+    /// What chaining `route`/`nest` onto `Self` produces. For a bare
+    /// router (e.g. [`EmptyRouter`]) this is a brand new [`Route`]; for a
+    /// [`Route`] itself it's `Route` again, with the new spec inserted
+    /// into the *same* shared tree rather than wrapped in another layer —
+    /// that's what lets a whole app's worth of `.route(...)` calls walk
+    /// one tree instead of chaining through `fallback` one per call.
+    type Routed;
+
+    fn route<T, B>(self, spec: &str, svc: T) -> Self::Routed
     where
-        T: Service<Request<Body>, Error = Infallible> + Clone;
+        T: Service<Request<Body>, Response = Response<B>, Error = Infallible> + Clone + Send + 'static,
+        T::Future: Send,
+        B: http_body::Body<Data = Bytes> + Send + Sync + 'static,
+        B::Error: Into<BoxError>;
+
+    /// Mounts `svc` — any service built from these combinators, including
+    /// another nested tree — under `prefix`. The prefix is stripped before
+    /// the request reaches `svc`, so its routes are written relative to
+    /// `prefix` exactly as if it were the top-level router; any params
+    /// captured while matching `prefix` are merged with whatever `svc`
+    /// itself captures, same as a plain [`route`](AddRoute::route) nested
+    /// one level deeper.
+    fn nest<T, B>(self, prefix: &str, svc: T) -> Self::Routed
+    where
+        T: Service<Request<Body>, Response = Response<B>, Error = Infallible> + Clone + Send + 'static,
+        T::Future: Send,
+        B: http_body::Body<Data = Bytes> + Send + Sync + 'static,
+        B::Error: Into<BoxError>;
 }
 
-impl<S, F> Route<S, F> {
+impl<F> Route<F> {
     pub fn boxed<B>(self) -> BoxRoute<B>
     where
         Self: Service<Request<Body>, Response = Response<B>, Error = Infallible> + Send + 'static,
         <Self as Service<Request<Body>>>::Future: Send,
         B: From<String> + 'static,
     {
-        ServiceBuilder::new()
-            .layer_fn(BoxRoute)
-            .buffer(1024)
-            .layer(BoxService::layer())
-            .service(self)
+        into_box_route(self)
     }
 
     pub fn layer<L>(self, layer: L) -> Layered<L::Service>
@@ -103,20 +208,44 @@ impl<S, F> Route<S, F> {
     }
 }
 
-impl<S, F> AddRoute for Route<S, F> {
-    fn route<T>(self, spec: &str, svc: T) -> Route<T, Self>
+impl<F> AddRoute for Route<F> {
+    type Routed = Route<F>;
+
+    fn route<T, B>(mut self, spec: &str, svc: T) -> Route<F>
     where
-        T: Service<Request<Body>, Error = Infallible> + Clone,
+        T: Service<Request<Body>, Response = Response<B>, Error = Infallible> + Clone + Send + 'static,
+        T::Future: Send,
+        B: http_body::Body<Data = Bytes> + Send + Sync + 'static,
+        B::Error: Into<BoxError>,
     {
-        Route {
-            pattern: PathPattern::new(spec),
-            svc,
-            fallback: self,
-        }
+        Arc::make_mut(&mut self.tree).insert(&parse_route_spec(spec), box_route(svc));
+        self
+    }
+
+    fn nest<T, B>(mut self, prefix: &str, svc: T) -> Route<F>
+    where
+        T: Service<Request<Body>, Response = Response<B>, Error = Infallible> + Clone + Send + 'static,
+        T::Future: Send,
+        B: http_body::Body<Data = Bytes> + Send + Sync + 'static,
+        B::Error: Into<BoxError>,
+    {
+        let mut spec = parse_route_spec(prefix);
+        spec.push(Segment::CatchAll(NESTED_TAIL_PARAM.to_string()));
+        Arc::make_mut(&mut self.tree).insert(&spec, box_route(Nested(svc)));
+        self
     }
 }
 
 impl<S, F> OnMethod<S, F> {
+    pub(crate) fn new(method: MethodFilter, svc: S, fallback: F) -> Self {
+        Self {
+            method,
+            svc,
+            fallback,
+            allowed: vec![method],
+        }
+    }
+
     pub fn get<H, B, T>(self, handler: H) -> OnMethod<handler::IntoService<H, B, T>, Self>
     where
         H: Handler<B, T>,
@@ -132,22 +261,35 @@ impl<S, F> OnMethod<S, F> {
     }
 
     pub fn on_method<T>(self, method: MethodFilter, svc: T) -> OnMethod<T, Self> {
+        let mut allowed = self.allowed.clone();
+        allowed.push(method);
         OnMethod {
             method,
             svc,
             fallback: self,
+            allowed,
+        }
+    }
+
+    /// Routes to `svc` when `guard` passes and falls back to `self`
+    /// otherwise, the same way [`on_method`](Self::on_method) branches on
+    /// [`MethodFilter`] but for any predicate over the request.
+    pub fn on<G, T>(self, guard: G, svc: T) -> OnGuard<T, Self>
+    where
+        G: Guard,
+    {
+        OnGuard {
+            guard: Arc::new(guard),
+            svc,
+            fallback: self,
         }
     }
 }
 
 // ===== Routing service impls =====
 
-impl<S, F, SB, FB> Service<Request<Body>> for Route<S, F>
+impl<F, FB> Service<Request<Body>> for Route<F>
 where
-    S: Service<Request<Body>, Response = Response<SB>, Error = Infallible> + Clone,
-    SB: http_body::Body<Data = Bytes> + Send + Sync + 'static,
-    SB::Error: Into<BoxError>,
-
     F: Service<Request<Body>, Response = Response<FB>, Error = Infallible> + Clone,
     FB: http_body::Body<Data = Bytes> + Send + Sync + 'static,
     FB::Error: Into<BoxError>,
@@ -157,7 +299,7 @@ where
 
     #[allow(clippy::type_complexity)]
     type Future = future::Either<
-        BoxResponseBody<Oneshot<S, Request<Body>>>,
+        Oneshot<BoxRoute<BoxBody>, Request<Body>>,
         BoxResponseBody<Oneshot<F, Request<Body>>>,
     >;
 
@@ -166,11 +308,15 @@ where
     }
 
     fn call(&mut self, mut req: Request<Body>) -> Self::Future {
-        if let Some(captures) = self.pattern.matches(req.uri().path()) {
-            insert_url_params(&mut req, captures);
-            let response_future = self.svc.clone().oneshot(req);
-            future::Either::Left(BoxResponseBody(response_future))
+        let segments = segments(req.uri().path());
+        let mut captures = checkout_captures();
+
+        if let Some(route) = self.tree.at(&segments, &mut captures) {
+            let route = route.clone();
+            insert_url_params(&mut req, captures.into_params());
+            future::Either::Left(route.oneshot(req))
         } else {
+            captures.release();
             let response_future = self.fallback.clone().oneshot(req);
             future::Either::Right(BoxResponseBody(response_future))
         }
@@ -180,16 +326,195 @@ where
 #[derive(Debug)]
 pub(crate) struct UrlParams(pub(crate) Vec<(String, String)>);
 
-fn insert_url_params<B>(req: &mut Request<B>, params: Vec<(String, String)>) {
+impl Drop for UrlParams {
+    /// Hands the now-unused capture buffer back to [`CAPTURE_POOL`] once the
+    /// request (and with it, these extensions) is dropped, so a later
+    /// request on this thread can reuse its allocation instead of starting
+    /// from an empty `Vec`.
+    fn drop(&mut self) {
+        release_captures(mem::take(&mut self.0));
+    }
+}
+
+thread_local! {
+    /// Per-thread pool of spare `(name, value)` capture buffers. Route
+    /// matching checks one out before walking the tree and, one way or
+    /// another, every buffer makes it back here: [`Route::call`] returns it
+    /// directly on a miss, and a hit hands it to [`UrlParams`], whose `Drop`
+    /// impl returns it once the request is done with it. This turns the
+    /// per-request `Vec` allocation on param-heavy routes into an
+    /// amortized-free pop from this pool after the first few requests.
+    static CAPTURE_POOL: RefCell<Vec<Vec<(String, String)>>> = RefCell::new(Vec::new());
+}
+
+fn checkout_captures() -> Captures {
+    Captures {
+        storage: CAPTURE_POOL.with(|pool| pool.borrow_mut().pop()).unwrap_or_default(),
+        len: 0,
+    }
+}
+
+fn release_captures(captures: Vec<(String, String)>) {
+    // The entries are kept, not cleared: `Captures::push` overwrites them in
+    // place on the next checkout, reusing their `String` allocations.
+    CAPTURE_POOL.with(|pool| pool.borrow_mut().push(captures));
+}
+
+/// A capture buffer built while walking the tree in [`Node::at`]. Behaves
+/// like a stack for the match/backtrack algorithm (`push`/`pop`), but
+/// reuses whatever `String` allocations are already sitting in `storage`
+/// — carried over from a previous, pooled use via [`checkout_captures`] —
+/// instead of freeing and reallocating a `String` per captured param on
+/// every request.
+#[derive(Default)]
+pub(crate) struct Captures {
+    storage: Vec<(String, String)>,
+    len: usize,
+}
+
+impl Captures {
+    fn push(&mut self, name: &str, value: &str) {
+        match self.storage.get_mut(self.len) {
+            Some((n, v)) => {
+                n.clear();
+                n.push_str(name);
+                v.clear();
+                v.push_str(value);
+            }
+            None => self.storage.push((name.to_string(), value.to_string())),
+        }
+        self.len += 1;
+    }
+
+    /// Like [`push`](Self::push), but builds the value by joining `parts`
+    /// with `/` in place — used for a catch-all's captured tail — instead
+    /// of allocating a fresh `String` via `[&str]::join`.
+    fn push_joined(&mut self, name: &str, parts: &[&str]) {
+        match self.storage.get_mut(self.len) {
+            Some((n, v)) => {
+                n.clear();
+                n.push_str(name);
+                v.clear();
+                for (i, part) in parts.iter().enumerate() {
+                    if i > 0 {
+                        v.push('/');
+                    }
+                    v.push_str(part);
+                }
+            }
+            None => self.storage.push((name.to_string(), parts.join("/"))),
+        }
+        self.len += 1;
+    }
+
+    fn pop(&mut self) {
+        debug_assert!(self.len > 0, "popped an empty Captures");
+        self.len -= 1;
+    }
+
+    /// Truncates off any spare, already-allocated slots beyond what this
+    /// match actually captured and returns the rest to move into
+    /// [`UrlParams`]. Exactly one buffer came out of the pool via
+    /// [`checkout_captures`] for this match, and [`UrlParams`]'s `Drop`
+    /// puts exactly one back — pooling the spare here too would push
+    /// twice per checkout and leak an entry into `CAPTURE_POOL` on every
+    /// matched request.
+    fn into_params(mut self) -> Vec<(String, String)> {
+        self.storage.truncate(self.len);
+        self.storage
+    }
+
+    /// Returns the whole buffer to the pool unused, e.g. when a lookup
+    /// misses entirely.
+    fn release(self) {
+        release_captures(self.storage);
+    }
+}
+
+fn insert_url_params<B>(req: &mut Request<B>, mut params: Vec<(String, String)>) {
     if let Some(current) = req.extensions_mut().get_mut::<Option<UrlParams>>() {
         let mut current = current.take().unwrap();
-        current.0.extend(params);
+        current.0.append(&mut params);
         req.extensions_mut().insert(Some(current));
+        // `params` is now empty but still holds its original allocation;
+        // hand it back instead of letting it drop on the way out.
+        release_captures(params);
     } else {
         req.extensions_mut().insert(Some(UrlParams(params)));
     }
 }
 
+/// Name under which [`AddRoute::nest`] stashes the unmatched path tail as a
+/// regular catch-all capture, so [`Nested`] can pull it back out of
+/// [`UrlParams`] and hand the inner router only the path beyond the prefix.
+const NESTED_TAIL_PARAM: &str = "__axum_nested_path";
+
+/// Removes the [`NESTED_TAIL_PARAM`] capture from `req`'s [`UrlParams`] (if
+/// any) and returns it, leaving behind whatever params were genuinely
+/// captured while matching the prefix for the inner router to see.
+fn take_nested_tail<B>(req: &mut Request<B>) -> String {
+    let current = match req.extensions_mut().get_mut::<Option<UrlParams>>() {
+        Some(current) => current,
+        None => return String::new(),
+    };
+    let mut params = current.take().unwrap();
+    let tail = match params.0.iter().position(|(name, _)| name == NESTED_TAIL_PARAM) {
+        Some(idx) => params.0.remove(idx).1,
+        None => String::new(),
+    };
+    *current = Some(params);
+    tail
+}
+
+/// Rebuilds `uri` with its path replaced by `tail`, preserving the query
+/// string. Used by [`Nested`] to present the inner router with the path
+/// that remains after the prefix has been stripped.
+///
+/// This runs on every request to a nested router, not at construction
+/// time, so an unexpected rewrite failure (the tail contains bytes that
+/// don't survive a round trip through `PathAndQuery`) falls back to the
+/// original `uri` rather than panicking.
+fn with_path(uri: &http::Uri, tail: &str) -> http::Uri {
+    let mut path_and_query = format!("/{}", tail);
+    if let Some(query) = uri.query() {
+        path_and_query.push('?');
+        path_and_query.push_str(query);
+    }
+
+    let rewritten = path_and_query.parse().ok().and_then(|path_and_query| {
+        let mut parts = uri.clone().into_parts();
+        parts.path_and_query = Some(path_and_query);
+        http::Uri::from_parts(parts).ok()
+    });
+    rewritten.unwrap_or_else(|| uri.clone())
+}
+
+/// The leaf service installed by [`AddRoute::nest`]. Before delegating to
+/// the wrapped router it strips the matched prefix off the request's path,
+/// so `svc`'s own routes can be written exactly as if it were mounted at
+/// the top level.
+#[derive(Clone)]
+struct Nested<S>(S);
+
+impl<S, B> Service<Request<Body>> for Nested<S>
+where
+    S: Service<Request<Body>, Response = Response<B>, Error = Infallible>,
+{
+    type Response = Response<B>;
+    type Error = Infallible;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.0.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<Body>) -> Self::Future {
+        let tail = take_nested_tail(&mut req);
+        *req.uri_mut() = with_path(req.uri(), &tail);
+        self.0.call(req)
+    }
+}
+
 impl<S, F, SB, FB> Service<Request<Body>> for OnMethod<S, F>
 where
     S: Service<Request<Body>, Response = Response<SB>, Error = Infallible> + Clone,
@@ -206,7 +531,10 @@ where
     #[allow(clippy::type_complexity)]
     type Future = future::Either<
         BoxResponseBody<Oneshot<S, Request<Body>>>,
-        BoxResponseBody<Oneshot<F, Request<Body>>>,
+        future::Either<
+            BoxResponseBody<Oneshot<F, Request<Body>>>,
+            future::Ready<Result<Response<BoxBody>, Infallible>>,
+        >,
     >;
 
     fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
@@ -217,6 +545,71 @@ where
         if self.method.matches(req.method()) {
             let response_future = self.svc.clone().oneshot(req);
             future::Either::Left(BoxResponseBody(response_future))
+        } else if self.allowed.iter().any(|m| m.matches(req.method())) {
+            // The path matched and some *other* registered method handles
+            // this request; keep walking the fallback chain to find it.
+            let response_future = self.fallback.clone().oneshot(req);
+            future::Either::Right(future::Either::Left(BoxResponseBody(response_future)))
+        } else {
+            // The path matched but no registered method does: 405, not 404.
+            future::Either::Right(future::Either::Right(future::ok(method_not_allowed(
+                &self.allowed,
+            ))))
+        }
+    }
+}
+
+fn method_not_allowed(allowed: &[MethodFilter]) -> Response<BoxBody> {
+    let mut res = Response::new(BoxBody::new(Body::empty()));
+    *res.status_mut() = StatusCode::METHOD_NOT_ALLOWED;
+    res.headers_mut()
+        .insert(http::header::ALLOW, allow_header_value(allowed));
+    res
+}
+
+fn allow_header_value(allowed: &[MethodFilter]) -> http::HeaderValue {
+    let mut methods = allowed
+        .iter()
+        .flat_map(|filter| filter.http_methods())
+        .cloned()
+        .collect::<Vec<_>>();
+    methods.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+    methods.dedup();
+    let methods = methods
+        .iter()
+        .map(|method| method.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    http::HeaderValue::from_str(&methods).unwrap_or_else(|_| http::HeaderValue::from_static(""))
+}
+
+impl<S, F, SB, FB> Service<Request<Body>> for OnGuard<S, F>
+where
+    S: Service<Request<Body>, Response = Response<SB>, Error = Infallible> + Clone,
+    SB: http_body::Body<Data = Bytes> + Send + Sync + 'static,
+    SB::Error: Into<BoxError>,
+
+    F: Service<Request<Body>, Response = Response<FB>, Error = Infallible> + Clone,
+    FB: http_body::Body<Data = Bytes> + Send + Sync + 'static,
+    FB::Error: Into<BoxError>,
+{
+    type Response = Response<BoxBody>;
+    type Error = Infallible;
+
+    #[allow(clippy::type_complexity)]
+    type Future = future::Either<
+        BoxResponseBody<Oneshot<S, Request<Body>>>,
+        BoxResponseBody<Oneshot<F, Request<Body>>>,
+    >;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        if self.guard.check(&req) {
+            let response_future = self.svc.clone().oneshot(req);
+            future::Either::Left(BoxResponseBody(response_future))
         } else {
             let response_future = self.fallback.clone().oneshot(req);
             future::Either::Right(BoxResponseBody(response_future))
@@ -224,6 +617,14 @@ where
     }
 }
 
+/// The generic equivalent of [`OnMethod::on_method`]'s dispatch: a
+/// [`Guard`] that passes only for requests using `filter`. Lets method
+/// matching be expressed as one instance of the same predicate mechanism
+/// that gates on host, headers, or anything else a [`Guard`] can inspect.
+pub fn method_guard(filter: MethodFilter) -> impl Guard {
+    move |req: &Request<Body>| filter.matches(req.method())
+}
+
 #[pin_project]
 pub struct BoxResponseBody<F>(#[pin] F);
 
@@ -249,14 +650,37 @@ where
 pub struct EmptyRouter;
 
 impl AddRoute for EmptyRouter {
-    fn route<S>(self, spec: &str, svc: S) -> Route<S, Self>
+    type Routed = Route<Self>;
+
+    fn route<T, B>(self, spec: &str, svc: T) -> Route<Self>
     where
-        S: Service<Request<Body>, Error = Infallible> + Clone,
+        T: Service<Request<Body>, Response = Response<B>, Error = Infallible> + Clone + Send + 'static,
+        T::Future: Send,
+        B: http_body::Body<Data = Bytes> + Send + Sync + 'static,
+        B::Error: Into<BoxError>,
     {
+        let mut tree = Node::default();
+        tree.insert(&parse_route_spec(spec), box_route(svc));
         Route {
-            pattern: PathPattern::new(spec),
-            svc,
             fallback: self,
+            tree: Arc::new(tree),
+        }
+    }
+
+    fn nest<T, B>(self, prefix: &str, svc: T) -> Route<Self>
+    where
+        T: Service<Request<Body>, Response = Response<B>, Error = Infallible> + Clone + Send + 'static,
+        T::Future: Send,
+        B: http_body::Body<Data = Bytes> + Send + Sync + 'static,
+        B::Error: Into<BoxError>,
+    {
+        let mut spec = parse_route_spec(prefix);
+        spec.push(Segment::CatchAll(NESTED_TAIL_PARAM.to_string()));
+        let mut tree = Node::default();
+        tree.insert(&spec, box_route(Nested(svc)));
+        Route {
+            fallback: self,
+            tree: Arc::new(tree),
         }
     }
 }
@@ -277,63 +701,225 @@ impl<R> Service<R> for EmptyRouter {
     }
 }
 
-// ===== PathPattern =====
+// ===== Router (radix tree) =====
+
+/// A single node of the path radix tree.
+///
+/// Children are resolved in priority order: static segments first, then the
+/// `:param` children in registration order, then the (at most one) catch-all
+/// child.
+/// Matching walks the request path one segment at a time, so lookup is
+/// `O(path length)` rather than trying every registered route in turn.
+#[derive(Default, Clone)]
+pub(crate) struct Node {
+    static_children: HashMap<String, Node>,
+    /// Every distinct `:param` registered at this position, tried in
+    /// registration order. Usually just one, but two routes can share a
+    /// param slot with different names or constraints (`/users/:id<[0-9]+>`
+    /// vs. `/users/:name<[a-z]+>`), so this can't collapse to a single
+    /// child without silently losing one of them.
+    param_children: Vec<Box<ParamChild>>,
+    catch_all_child: Option<Box<CatchAllChild>>,
+    endpoint: Option<BoxRoute<BoxBody>>,
+}
+
+#[derive(Clone)]
+struct ParamChild {
+    name: String,
+    /// Inline constraint from a `:name<pattern>` segment, anchored so it
+    /// must match the whole segment rather than a prefix of it. `None` for
+    /// a plain `:name` segment, which accepts any segment at all,
+    /// including an empty one (e.g. `/users/` matches `/users/:id` with
+    /// `id` captured as `""`).
+    constraint: Option<Regex>,
+    node: Node,
+}
+
+/// A catch-all (`*name`) child. Unlike [`ParamChild`] it has no further
+/// children of its own: once a catch-all segment matches, it always
+/// consumes the rest of the path, so it is the lookup's terminal node.
+#[derive(Clone)]
+struct CatchAllChild {
+    name: String,
+    endpoint: BoxRoute<BoxBody>,
+}
 
 #[derive(Debug, Clone)]
-pub(crate) struct PathPattern(Arc<Inner>);
+enum Segment {
+    Static(String),
+    Param(String, Option<Regex>),
+    CatchAll(String),
+}
 
-#[derive(Debug)]
-struct Inner {
-    full_path_regex: Regex,
-    capture_group_names: Box<[Bytes]>,
+fn parse_route_spec(spec: &str) -> Vec<Segment> {
+    let parts = spec.split('/').collect::<Vec<_>>();
+    let last = parts.len() - 1;
+    parts
+        .into_iter()
+        .enumerate()
+        .map(|(idx, part)| {
+            if let Some(name) = part.strip_prefix(':') {
+                let (name, constraint) = parse_param_constraint(name);
+                Segment::Param(name, constraint)
+            } else if let Some(name) = part.strip_prefix('*') {
+                assert!(
+                    idx == last,
+                    "catch-all segment `*{}` must be the last segment in the route spec",
+                    name
+                );
+                Segment::CatchAll(name.to_string())
+            } else {
+                Segment::Static(part.to_string())
+            }
+        })
+        .collect()
+}
+
+/// Splits a `:name` segment's body into its name and an optional inline
+/// `<pattern>` constraint, e.g. `id<[0-9]+>` -> (`id`, Some(`^(?:[0-9]+)$`)).
+/// An invalid constraint pattern fails loudly here, at route construction
+/// time, rather than silently never matching at request time.
+fn parse_param_constraint(name: &str) -> (String, Option<Regex>) {
+    match name.find('<') {
+        None => (name.to_string(), None),
+        Some(start) => {
+            let name_part = &name[..start];
+            let pattern = name[start + 1..]
+                .strip_suffix('>')
+                .expect("invalid param constraint: missing closing `>`");
+            let anchored = format!("^(?:{})$", pattern);
+            let regex = Regex::new(&anchored).expect("invalid regex in param constraint");
+            (name_part.to_string(), Some(regex))
+        }
+    }
 }
 
-impl PathPattern {
-    pub(crate) fn new(pattern: &str) -> Self {
-        let mut capture_group_names = Vec::new();
+/// `Regex` has no `PartialEq`, so two param constraints are compared by
+/// the pattern they were built from.
+fn regex_pattern(constraint: &Option<Regex>) -> Option<&str> {
+    constraint.as_ref().map(Regex::as_str)
+}
 
-        let pattern = pattern
-            .split('/')
-            .map(|part| {
-                if let Some(key) = part.strip_prefix(':') {
-                    capture_group_names.push(Bytes::copy_from_slice(key.as_bytes()));
+fn segments(path: &str) -> Vec<&str> {
+    path.split('/').collect()
+}
 
-                    Cow::Owned(format!("(?P<{}>[^/]*)", key))
+impl Node {
+    fn insert(&mut self, segments: &[Segment], endpoint: BoxRoute<BoxBody>) {
+        match segments.split_first() {
+            None => {
+                // Once the tree is shared across every `route()` call (see
+                // `AddRoute for Route<F>`), silently overwriting here would
+                // drop whichever handler was registered first — e.g.
+                // `.route("/x", get(a)).route("/x", post(b))` would quietly
+                // lose `a` entirely rather than serving both methods, and a
+                // GET to `/x` would come back `405` instead of reaching
+                // `a`. Both registrations are already type-erased `BoxRoute`s
+                // by this point, so there's no `MethodFilter`/`allowed` left
+                // to merge them by; fail loudly here instead, the same way
+                // an invalid route spec fails at construction time, and
+                // point at the fix: combine methods in one `route()` call
+                // (`get(a).post(b)`) instead of registering the same path
+                // twice.
+                assert!(
+                    self.endpoint.is_none(),
+                    "duplicate route registered for the same path; combine the methods into a \
+                     single `route()` call (e.g. `get(a).post(b)`) instead of calling `route()` \
+                     more than once for the same path"
+                );
+                self.endpoint = Some(endpoint);
+            }
+            Some((Segment::Static(part), rest)) => self
+                .static_children
+                .entry(part.clone())
+                .or_insert_with(Node::default)
+                .insert(rest, endpoint),
+            Some((Segment::Param(name, constraint), rest)) => {
+                let existing = self.param_children.iter_mut().find(|child| {
+                    child.name == *name && regex_pattern(&child.constraint) == regex_pattern(constraint)
+                });
+                let child = match existing {
+                    Some(child) => child,
+                    None => {
+                        self.param_children.push(Box::new(ParamChild {
+                            name: name.clone(),
+                            constraint: constraint.clone(),
+                            node: Node::default(),
+                        }));
+                        self.param_children.last_mut().unwrap()
+                    }
+                };
+                child.node.insert(rest, endpoint);
+            }
+            Some((Segment::CatchAll(name), _rest)) => {
+                if name == NESTED_TAIL_PARAM {
+                    self.catch_all_child = Some(Box::new(CatchAllChild {
+                        name: name.clone(),
+                        endpoint: endpoint.clone(),
+                    }));
+                    // Mounting exactly at the prefix, with no further
+                    // segments at all (e.g. `nest("/api/v1", ...)` matching
+                    // a request for exactly `/api/v1`, not just
+                    // `/api/v1/...`), should still reach the catch-all
+                    // endpoint with an empty tail, rather than 404 only
+                    // because there's no trailing segment for the
+                    // catch-all to consume. This only applies to `nest()`'s
+                    // internal tail param — a plain `*name` catch-all route
+                    // still requires at least the prefix boundary, since
+                    // its handler expects `name` to actually be captured.
+                    self.endpoint = Some(endpoint);
                 } else {
-                    Cow::Borrowed(part)
+                    self.catch_all_child = Some(Box::new(CatchAllChild {
+                        name: name.clone(),
+                        endpoint,
+                    }));
                 }
-            })
-            .join("/");
-
-        let full_path_regex =
-            Regex::new(&format!("^{}$", pattern)).expect("invalid regex generated from route");
-
-        Self(Arc::new(Inner {
-            full_path_regex,
-            capture_group_names: capture_group_names.into(),
-        }))
-    }
-
-    pub(crate) fn matches(&self, path: &str) -> Option<Captures> {
-        self.0.full_path_regex.captures(path).map(|captures| {
-            let captures = self
-                .0
-                .capture_group_names
-                .iter()
-                .map(|bytes| {
-                    std::str::from_utf8(bytes)
-                        .expect("bytes were created from str so is valid utf-8")
-                })
-                .filter_map(|name| captures.name(name).map(|value| (name, value.as_str())))
-                .map(|(key, value)| (key.to_string(), value.to_string()))
-                .collect::<Vec<_>>();
-
-            captures
-        })
+            }
+        }
     }
-}
 
-type Captures = Vec<(String, String)>;
+    /// Walks `path_segments` against this subtree, collecting captured
+    /// `:param` values into `captures` along the way. Static matches are
+    /// tried before the param children, and a dead end backtracks to the next
+    /// candidate rather than failing the whole lookup.
+    fn at<'n>(
+        &'n self,
+        path_segments: &[&str],
+        captures: &mut Captures,
+    ) -> Option<&'n BoxRoute<BoxBody>> {
+        match path_segments.split_first() {
+            None => self.endpoint.as_ref(),
+            Some((first, rest)) => {
+                if let Some(child) = self.static_children.get(*first) {
+                    if let Some(found) = child.at(rest, captures) {
+                        return Some(found);
+                    }
+                }
+
+                for param in &self.param_children {
+                    let satisfies_constraint = param
+                        .constraint
+                        .as_ref()
+                        .map_or(true, |constraint| constraint.is_match(first));
+                    if satisfies_constraint {
+                        captures.push(&param.name, first);
+                        if let Some(found) = param.node.at(rest, captures) {
+                            return Some(found);
+                        }
+                        captures.pop();
+                    }
+                }
+
+                if let Some(catch_all) = &self.catch_all_child {
+                    captures.push_joined(&catch_all.name, path_segments);
+                    return Some(&catch_all.endpoint);
+                }
+
+                None
+            }
+        }
+    }
+}
 
 // ===== BoxRoute =====
 
@@ -345,15 +931,41 @@ impl<B> Clone for BoxRoute<B> {
     }
 }
 
-impl<B> AddRoute for BoxRoute<B> {
-    fn route<S>(self, spec: &str, svc: S) -> Route<S, Self>
+impl<B> AddRoute for BoxRoute<B>
+where
+    B: From<String> + 'static,
+{
+    type Routed = Route<Self>;
+
+    fn route<T, RB>(self, spec: &str, svc: T) -> Route<Self>
     where
-        S: Service<Request<Body>, Error = Infallible> + Clone,
+        T: Service<Request<Body>, Response = Response<RB>, Error = Infallible> + Clone + Send + 'static,
+        T::Future: Send,
+        RB: http_body::Body<Data = Bytes> + Send + Sync + 'static,
+        RB::Error: Into<BoxError>,
     {
+        let mut tree = Node::default();
+        tree.insert(&parse_route_spec(spec), box_route(svc));
+        Route {
+            fallback: self,
+            tree: Arc::new(tree),
+        }
+    }
+
+    fn nest<T, RB>(self, prefix: &str, svc: T) -> Route<Self>
+    where
+        T: Service<Request<Body>, Response = Response<RB>, Error = Infallible> + Clone + Send + 'static,
+        T::Future: Send,
+        RB: http_body::Body<Data = Bytes> + Send + Sync + 'static,
+        RB::Error: Into<BoxError>,
+    {
+        let mut spec = parse_route_spec(prefix);
+        spec.push(Segment::CatchAll(NESTED_TAIL_PARAM.to_string()));
+        let mut tree = Node::default();
+        tree.insert(&spec, box_route(Nested(svc)));
         Route {
-            pattern: PathPattern::new(spec),
-            svc,
             fallback: self,
+            tree: Arc::new(tree),
         }
     }
 }
@@ -434,20 +1046,92 @@ where
         .unwrap()
 }
 
+fn into_box_route<S, B>(svc: S) -> BoxRoute<B>
+where
+    S: Service<Request<Body>, Response = Response<B>, Error = Infallible> + Send + 'static,
+    S::Future: Send,
+    B: From<String> + 'static,
+{
+    ServiceBuilder::new()
+        .layer_fn(BoxRoute)
+        .buffer(1024)
+        .layer(BoxService::layer())
+        .service(svc)
+}
+
+/// Adapts an arbitrary route service into the `BoxRoute<BoxBody>` leaves
+/// stored in the radix tree, mapping its response body into [`BoxBody`]
+/// first so the bound on [`into_box_route`] is satisfied regardless of the
+/// service's own body type.
+fn box_route<T, B>(svc: T) -> BoxRoute<BoxBody>
+where
+    T: Service<Request<Body>, Response = Response<B>, Error = Infallible> + Clone + Send + 'static,
+    T::Future: Send,
+    B: http_body::Body<Data = Bytes> + Send + Sync + 'static,
+    B::Error: Into<BoxError>,
+{
+    into_box_route(MapResponseBody(svc))
+}
+
+#[derive(Clone)]
+struct MapResponseBody<S>(S);
+
+impl<S, B> Service<Request<Body>> for MapResponseBody<S>
+where
+    S: Service<Request<Body>, Response = Response<B>, Error = Infallible> + Clone,
+    B: http_body::Body<Data = Bytes> + Send + Sync + 'static,
+    B::Error: Into<BoxError>,
+{
+    type Response = Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxResponseBody<Oneshot<S, Request<Body>>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        BoxResponseBody(self.0.clone().oneshot(req))
+    }
+}
+
 // ===== Layered =====
 
 #[derive(Clone, Debug)]
 pub struct Layered<S>(S);
 
 impl<S> AddRoute for Layered<S> {
-    fn route<T>(self, spec: &str, svc: T) -> Route<T, Self>
+    type Routed = Route<Self>;
+
+    fn route<T, B>(self, spec: &str, svc: T) -> Route<Self>
     where
-        T: Service<Request<Body>, Error = Infallible> + Clone,
+        T: Service<Request<Body>, Response = Response<B>, Error = Infallible> + Clone + Send + 'static,
+        T::Future: Send,
+        B: http_body::Body<Data = Bytes> + Send + Sync + 'static,
+        B::Error: Into<BoxError>,
     {
+        let mut tree = Node::default();
+        tree.insert(&parse_route_spec(spec), box_route(svc));
         Route {
-            pattern: PathPattern::new(spec),
-            svc,
             fallback: self,
+            tree: Arc::new(tree),
+        }
+    }
+
+    fn nest<T, B>(self, prefix: &str, svc: T) -> Route<Self>
+    where
+        T: Service<Request<Body>, Response = Response<B>, Error = Infallible> + Clone + Send + 'static,
+        T::Future: Send,
+        B: http_body::Body<Data = Bytes> + Send + Sync + 'static,
+        B::Error: Into<BoxError>,
+    {
+        let mut spec = parse_route_spec(prefix);
+        spec.push(Segment::CatchAll(NESTED_TAIL_PARAM.to_string()));
+        let mut tree = Node::default();
+        tree.insert(&spec, box_route(Nested(svc)));
+        Route {
+            fallback: self,
+            tree: Arc::new(tree),
         }
     }
 }
@@ -491,14 +1175,37 @@ pub struct HandleError<S, F> {
 }
 
 impl<S, F> AddRoute for HandleError<S, F> {
-    fn route<T>(self, spec: &str, svc: T) -> Route<T, Self>
+    type Routed = Route<Self>;
+
+    fn route<T, B>(self, spec: &str, svc: T) -> Route<Self>
     where
-        T: Service<Request<Body>, Error = Infallible> + Clone,
+        T: Service<Request<Body>, Response = Response<B>, Error = Infallible> + Clone + Send + 'static,
+        T::Future: Send,
+        B: http_body::Body<Data = Bytes> + Send + Sync + 'static,
+        B::Error: Into<BoxError>,
     {
+        let mut tree = Node::default();
+        tree.insert(&parse_route_spec(spec), box_route(svc));
         Route {
-            pattern: PathPattern::new(spec),
-            svc,
             fallback: self,
+            tree: Arc::new(tree),
+        }
+    }
+
+    fn nest<T, B>(self, prefix: &str, svc: T) -> Route<Self>
+    where
+        T: Service<Request<Body>, Response = Response<B>, Error = Infallible> + Clone + Send + 'static,
+        T::Future: Send,
+        B: http_body::Body<Data = Bytes> + Send + Sync + 'static,
+        B::Error: Into<BoxError>,
+    {
+        let mut spec = parse_route_spec(prefix);
+        spec.push(Segment::CatchAll(NESTED_TAIL_PARAM.to_string()));
+        let mut tree = Node::default();
+        tree.insert(&spec, box_route(Nested(svc)));
+        Route {
+            fallback: self,
+            tree: Arc::new(tree),
         }
     }
 }
@@ -581,10 +1288,305 @@ mod tests {
         refute_match("/users/:id", "/users/42/action");
     }
 
+    #[test]
+    #[should_panic(expected = "duplicate route registered")]
+    fn test_duplicate_path_registration_panics_instead_of_dropping_a_handler() {
+        EmptyRouter.route("/x", dummy_route()).route("/x", dummy_route());
+    }
+
+    #[test]
+    fn test_route_calls_share_one_tree() {
+        let router = EmptyRouter.route("/a", dummy_route()).route("/b", dummy_route());
+
+        let mut captures = Captures::default();
+        assert!(
+            router.tree.at(&segments("/a"), &mut captures).is_some(),
+            "first route() call should still be reachable after a second one"
+        );
+        assert!(
+            router.tree.at(&segments("/b"), &mut captures).is_some(),
+            "second route() call should be inserted into the same tree as the first"
+        );
+    }
+
+    #[test]
+    fn test_method_not_allowed_lists_allowed_methods() {
+        let allowed = vec![MethodFilter::Get, MethodFilter::Post];
+        let res = method_not_allowed(&allowed);
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(res.headers()[http::header::ALLOW], "GET, POST");
+    }
+
+    #[test]
+    fn test_allow_header_value_dedupes_any_against_specific_methods() {
+        let allowed = vec![MethodFilter::Any, MethodFilter::Get];
+        let value = allow_header_value(&allowed);
+        let methods = value.to_str().unwrap().split(", ").collect::<Vec<_>>();
+
+        assert_eq!(methods.iter().filter(|m| **m == "GET").count(), 1);
+        assert_eq!(methods.len(), 9, "one entry per distinct HTTP method, no repeats");
+    }
+
+    #[test]
+    fn test_static_beats_param() {
+        let mut tree = Node::default();
+        tree.insert(&parse_route_spec("/users/:id"), dummy_route());
+        tree.insert(&parse_route_spec("/users/me"), dummy_route());
+
+        let mut captures = Captures::default();
+        tree.at(&segments("/users/me"), &mut captures)
+            .expect("should match");
+        assert!(
+            captures.into_params().is_empty(),
+            "the static `/users/me` route should win over `/users/:id`"
+        );
+
+        let mut captures = Captures::default();
+        tree.at(&segments("/users/1"), &mut captures)
+            .expect("should match");
+        assert_eq!(captures.into_params(), vec![("id".to_string(), "1".to_string())]);
+    }
+
+    #[test]
+    fn test_catch_all() {
+        let mut tree = Node::default();
+        tree.insert(&parse_route_spec("/static/*path"), dummy_route());
+
+        let mut captures = Captures::default();
+        tree.at(&segments("/static/css/site.css"), &mut captures)
+            .expect("should match");
+        assert_eq!(
+            captures.into_params(),
+            vec![("path".to_string(), "css/site.css".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_catch_all_does_not_match_bare_prefix() {
+        let mut tree = Node::default();
+        tree.insert(&parse_route_spec("/static/*path"), dummy_route());
+
+        // Unlike `nest()`'s internal tail-param catch-all, a plain `*name`
+        // catch-all route's handler expects `name` to actually be
+        // captured, so a request to the bare prefix (no trailing segment)
+        // should not match at all.
+        refute_match("/static/*path", "/static");
+    }
+
+    #[test]
+    #[should_panic(expected = "must be the last segment")]
+    fn test_catch_all_must_be_last() {
+        parse_route_spec("/static/*path/extra");
+    }
+
+    #[test]
+    fn test_constrained_param() {
+        let mut tree = Node::default();
+        tree.insert(&parse_route_spec("/users/:id<[0-9]+>"), dummy_route());
+
+        assert_match("/users/:id<[0-9]+>", "/users/42");
+        refute_match("/users/:id<[0-9]+>", "/users/not-a-number");
+
+        let mut captures = Captures::default();
+        tree.at(&segments("/users/42"), &mut captures)
+            .expect("should match");
+        assert_eq!(captures.into_params(), vec![("id".to_string(), "42".to_string())]);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid regex")]
+    fn test_invalid_param_constraint() {
+        parse_route_spec("/users/:id<(>");
+    }
+
+    #[test]
+    fn test_distinct_params_at_same_position_both_reachable() {
+        let mut tree = Node::default();
+        tree.insert(&parse_route_spec("/users/:id<[0-9]+>"), dummy_route());
+        tree.insert(&parse_route_spec("/users/:name<[a-z]+>"), dummy_route());
+
+        let mut captures = Captures::default();
+        tree.at(&segments("/users/42"), &mut captures)
+            .expect("numeric id should match the first param");
+        assert_eq!(captures.into_params(), vec![("id".to_string(), "42".to_string())]);
+
+        let mut captures = Captures::default();
+        tree.at(&segments("/users/alice"), &mut captures)
+            .expect("alphabetic name should match the second param");
+        assert_eq!(captures.into_params(), vec![("name".to_string(), "alice".to_string())]);
+    }
+
+    #[test]
+    fn test_nest_prefix_tree_captures_tail() {
+        let mut tree = Node::default();
+        let mut spec = parse_route_spec("/api/v1");
+        spec.push(Segment::CatchAll(NESTED_TAIL_PARAM.to_string()));
+        tree.insert(&spec, dummy_route());
+
+        let mut captures = Captures::default();
+        tree.at(&segments("/api/v1/users/42"), &mut captures)
+            .expect("should match");
+        assert_eq!(
+            captures.into_params(),
+            vec![(NESTED_TAIL_PARAM.to_string(), "users/42".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_nest_matches_bare_prefix_with_empty_tail() {
+        let mut tree = Node::default();
+        let mut spec = parse_route_spec("/api/v1");
+        spec.push(Segment::CatchAll(NESTED_TAIL_PARAM.to_string()));
+        tree.insert(&spec, dummy_route());
+
+        let mut captures = Captures::default();
+        tree.at(&segments("/api/v1"), &mut captures)
+            .expect("the bare prefix, with no trailing segment, should still match");
+    }
+
+    #[test]
+    fn test_take_nested_tail_leaves_other_params() {
+        let mut req = Request::new(Body::empty());
+        insert_url_params(
+            &mut req,
+            vec![
+                ("id".to_string(), "1".to_string()),
+                (NESTED_TAIL_PARAM.to_string(), "users/42".to_string()),
+            ],
+        );
+
+        let tail = take_nested_tail(&mut req);
+        assert_eq!(tail, "users/42");
+
+        let remaining = req
+            .extensions()
+            .get::<Option<UrlParams>>()
+            .unwrap()
+            .as_ref()
+            .unwrap();
+        assert_eq!(remaining.0, vec![("id".to_string(), "1".to_string())]);
+    }
+
+    #[test]
+    fn test_with_path_preserves_query() {
+        let uri: http::Uri = "/api/v1/users/42?verbose=true".parse().unwrap();
+        let rewritten = with_path(&uri, "users/42");
+        assert_eq!(rewritten.path(), "/users/42");
+        assert_eq!(rewritten.query(), Some("verbose=true"));
+    }
+
+    #[test]
+    fn test_with_path_falls_back_to_original_uri_on_invalid_tail() {
+        let uri: http::Uri = "/api/v1/users?verbose=true".parse().unwrap();
+        // A bare CR is not a legal `PathAndQuery` byte, so the rewrite must
+        // fail and fall back instead of panicking.
+        let rewritten = with_path(&uri, "bad\rtail");
+        assert_eq!(rewritten, uri);
+    }
+
+    #[test]
+    fn test_guard_closure() {
+        let has_api_key = |req: &Request<Body>| req.headers().contains_key("x-api-key");
+
+        let with_key = Request::builder()
+            .header("x-api-key", "secret")
+            .body(Body::empty())
+            .unwrap();
+        let without_key = Request::new(Body::empty());
+
+        assert!(has_api_key.check(&with_key));
+        assert!(!has_api_key.check(&without_key));
+    }
+
+    #[test]
+    fn test_method_guard_matches_method_filter() {
+        let guard = method_guard(MethodFilter::Post);
+        let get_req = Request::builder()
+            .method(Method::GET)
+            .body(Body::empty())
+            .unwrap();
+        let post_req = Request::builder()
+            .method(Method::POST)
+            .body(Body::empty())
+            .unwrap();
+
+        assert!(!guard.check(&get_req));
+        assert!(guard.check(&post_req));
+    }
+
+    #[test]
+    fn test_capture_buffer_is_recycled() {
+        let mut captures = checkout_captures();
+        captures.push("id", "1");
+        let params = captures.into_params();
+        let capacity = params.capacity();
+        release_captures(params);
+
+        let recycled = checkout_captures();
+        let recycled_params = recycled.into_params();
+        assert!(recycled_params.is_empty());
+        assert!(recycled_params.capacity() >= capacity);
+        release_captures(recycled_params);
+    }
+
+    #[test]
+    fn test_insert_url_params_returns_merged_buffer_to_pool() {
+        let before = CAPTURE_POOL.with(|pool| pool.borrow().len());
+
+        let mut req = Request::new(Body::empty());
+        insert_url_params(&mut req, vec![("id".to_string(), "1".to_string())]);
+        insert_url_params(
+            &mut req,
+            vec![(NESTED_TAIL_PARAM.to_string(), "tail".to_string())],
+        );
+
+        let after = CAPTURE_POOL.with(|pool| pool.borrow().len());
+        assert_eq!(
+            after,
+            before + 1,
+            "the second buffer's allocation should be handed back to the pool once merged, not dropped"
+        );
+    }
+
+    #[test]
+    fn test_capture_pool_stays_bounded_across_matched_requests() {
+        let mut tree = Node::default();
+        tree.insert(&parse_route_spec("/users/:id"), dummy_route());
+
+        let before = CAPTURE_POOL.with(|pool| pool.borrow().len());
+
+        for _ in 0..50 {
+            // Mirrors `Route::call`'s hit path: one checkout, a match, the
+            // resulting params handed to a request, then `UrlParams`'s
+            // `Drop` (simulated here by `release_captures`) returning the
+            // single buffer to the pool.
+            let mut captures = checkout_captures();
+            tree.at(&segments("/users/42"), &mut captures)
+                .expect("should match");
+            let params = captures.into_params();
+            release_captures(params);
+        }
+
+        let after = CAPTURE_POOL.with(|pool| pool.borrow().len());
+        assert!(
+            after <= before + 1,
+            "pool should stay bounded across repeated matched requests, not gain an entry per \
+             request (before: {}, after: {})",
+            before,
+            after
+        );
+    }
+
+    fn dummy_route() -> BoxRoute<BoxBody> {
+        box_route(EmptyRouter)
+    }
+
     fn assert_match(route_spec: &'static str, path: &'static str) {
-        let route = PathPattern::new(route_spec);
+        let mut tree = Node::default();
+        tree.insert(&parse_route_spec(route_spec), dummy_route());
+        let mut captures = Captures::default();
         assert!(
-            route.matches(path).is_some(),
+            tree.at(&segments(path), &mut captures).is_some(),
             "`{}` doesn't match `{}`",
             path,
             route_spec
@@ -592,9 +1594,11 @@ mod tests {
     }
 
     fn refute_match(route_spec: &'static str, path: &'static str) {
-        let route = PathPattern::new(route_spec);
+        let mut tree = Node::default();
+        tree.insert(&parse_route_spec(route_spec), dummy_route());
+        let mut captures = Captures::default();
         assert!(
-            route.matches(path).is_none(),
+            tree.at(&segments(path), &mut captures).is_none(),
             "`{}` did match `{}` (but shouldn't)",
             path,
             route_spec